@@ -0,0 +1,229 @@
+use crate::http::HttpClient;
+use crate::{Client, Error};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use time::OffsetDateTime;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+const EVENTSUB_WS_URL: &str = "wss://eventsub.wss.twitch.tv/ws";
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// A strongly-typed EventSub notification
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// `stream.online`: the broadcaster started streaming
+    StreamOnline {
+        broadcaster_user_id: String,
+        started_at: OffsetDateTime,
+    },
+    /// `stream.offline`: the broadcaster stopped streaming
+    StreamOffline { broadcaster_user_id: String },
+}
+
+#[derive(Deserialize)]
+struct Frame {
+    metadata: Metadata,
+    payload: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    message_type: String,
+}
+
+#[derive(Deserialize)]
+struct Session {
+    id: String,
+    #[serde(default)]
+    reconnect_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SessionPayload {
+    session: Session,
+}
+
+#[derive(Deserialize)]
+struct NotificationPayload {
+    subscription: SubscriptionInfo,
+    event: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct SubscriptionInfo {
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Deserialize)]
+struct StreamOnlineEvent {
+    broadcaster_user_id: String,
+    #[serde(deserialize_with = "crate::assume_utc_date_time")]
+    started_at: OffsetDateTime,
+}
+
+#[derive(Deserialize)]
+struct StreamOfflineEvent {
+    broadcaster_user_id: String,
+}
+
+impl<C: HttpClient + 'static> Client<C> {
+    /// Subscribe to `stream.online`/`stream.offline` for `broadcaster_user_id` over EventSub
+    ///
+    /// Connects the WebSocket transport, registers both subscriptions against the session Twitch
+    /// hands back in `session_welcome`, and returns a stream of decoded [`Event`]s. A
+    /// `session_reconnect` is followed transparently, since Twitch carries existing
+    /// subscriptions over to the new session on its own.
+    pub async fn subscribe_stream_events(
+        &self,
+        broadcaster_user_id: impl Into<String>,
+    ) -> Result<Pin<Box<dyn futures::Stream<Item = Result<Event, Error<C>>> + Send>>, Error<C>>
+    {
+        let broadcaster_user_id = broadcaster_user_id.into();
+        let (ws, session_id) = connect_and_welcome::<C>(EVENTSUB_WS_URL).await?;
+
+        self.create_eventsub_subscription("stream.online", &broadcaster_user_id, &session_id)
+            .await?;
+        self.create_eventsub_subscription("stream.offline", &broadcaster_user_id, &session_id)
+            .await?;
+
+        Ok(Box::pin(futures::stream::unfold(ws, next_event::<C>)))
+    }
+
+    async fn create_eventsub_subscription(
+        &self,
+        kind: &'static str,
+        broadcaster_user_id: &str,
+        session_id: &str,
+    ) -> Result<(), Error<C>> {
+        #[derive(Serialize)]
+        struct Condition<'a> {
+            broadcaster_user_id: &'a str,
+        }
+
+        #[derive(Serialize)]
+        struct Transport<'a> {
+            method: &'static str,
+            session_id: &'a str,
+        }
+
+        #[derive(Serialize)]
+        struct Request<'a> {
+            #[serde(rename = "type")]
+            kind: &'static str,
+            version: &'static str,
+            condition: Condition<'a>,
+            transport: Transport<'a>,
+        }
+
+        #[derive(Deserialize)]
+        struct Response {}
+
+        self.post_response::<Response, _>(
+            "eventsub/subscriptions",
+            &Request {
+                kind,
+                version: "1",
+                condition: Condition { broadcaster_user_id },
+                transport: Transport {
+                    method: "websocket",
+                    session_id,
+                },
+            },
+        )
+        .await
+        .map(drop)
+    }
+}
+
+async fn connect_and_welcome<C: HttpClient>(url: &str) -> Result<(WsStream, String), Error<C>> {
+    let (mut ws, _) = connect_async(url)
+        .await
+        .map_err(|error| Error::WebSocket { error: Box::new(error) })?;
+    loop {
+        match read_frame::<C>(&mut ws).await? {
+            Some(frame) if frame.metadata.message_type == "session_welcome" => {
+                let welcome: SessionPayload =
+                    serde_json::from_value(frame.payload).map_err(|error| Error::Json { error })?;
+                return Ok((ws, welcome.session.id));
+            }
+            _ => continue,
+        }
+    }
+}
+
+async fn next_event<C: HttpClient>(mut ws: WsStream) -> Option<(Result<Event, Error<C>>, WsStream)> {
+    loop {
+        let frame = match read_frame::<C>(&mut ws).await {
+            Ok(Some(frame)) => frame,
+            Ok(None) => continue,
+            Err(error) => return Some((Err(error), ws)),
+        };
+
+        match frame.metadata.message_type.as_str() {
+            "session_keepalive" => continue,
+            "session_reconnect" => {
+                let reconnect: SessionPayload = match serde_json::from_value(frame.payload) {
+                    Ok(reconnect) => reconnect,
+                    Err(error) => return Some((Err(Error::Json { error }), ws)),
+                };
+                let Some(url) = reconnect.session.reconnect_url else {
+                    continue;
+                };
+                match connect_async(&url).await {
+                    Ok((new_ws, _)) => ws = new_ws,
+                    Err(error) => return Some((Err(Error::WebSocket { error: Box::new(error) }), ws)),
+                }
+            }
+            "notification" => {
+                let notification: NotificationPayload = match serde_json::from_value(frame.payload)
+                {
+                    Ok(notification) => notification,
+                    Err(error) => return Some((Err(Error::Json { error }), ws)),
+                };
+                match decode_event(notification) {
+                    Ok(Some(event)) => return Some((Ok(event), ws)),
+                    Ok(None) => continue,
+                    Err(error) => return Some((Err(error), ws)),
+                }
+            }
+            _ => continue,
+        }
+    }
+}
+
+fn decode_event<C: HttpClient>(notification: NotificationPayload) -> Result<Option<Event>, Error<C>> {
+    let event = match notification.subscription.kind.as_str() {
+        "stream.online" => {
+            let payload: StreamOnlineEvent = serde_json::from_value(notification.event)
+                .map_err(|error| Error::Json { error })?;
+            Event::StreamOnline {
+                broadcaster_user_id: payload.broadcaster_user_id,
+                started_at: payload.started_at,
+            }
+        }
+        "stream.offline" => {
+            let payload: StreamOfflineEvent = serde_json::from_value(notification.event)
+                .map_err(|error| Error::Json { error })?;
+            Event::StreamOffline {
+                broadcaster_user_id: payload.broadcaster_user_id,
+            }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(event))
+}
+
+async fn read_frame<C: HttpClient>(ws: &mut WsStream) -> Result<Option<Frame>, Error<C>> {
+    match ws.next().await {
+        Some(Ok(Message::Text(text))) => {
+            serde_json::from_str(&text).map(Some).map_err(|error| Error::Json { error })
+        }
+        Some(Ok(_)) => Ok(None),
+        Some(Err(error)) => Err(Error::WebSocket { error: Box::new(error) }),
+        None => Err(Error::WebSocketClosed),
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+/// The HTTP method a [`Request`] is sent with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+}
+
+/// A backend-agnostic HTTP request
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: Method,
+    pub url: String,
+    pub headers: HashMap<String, String>,
+    pub body: Option<Vec<u8>>,
+}
+
+/// A backend-agnostic HTTP response
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Was the response a `2xx`?
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Deserialize the body as JSON
+    pub fn json<T>(&self) -> Result<T, serde_json::Error>
+    where
+        for<'de> T: serde::Deserialize<'de>,
+    {
+        serde_json::from_slice(&self.body)
+    }
+}
+
+/// Abstracts the HTTP backend a [`crate::Client`] executes requests through
+///
+/// Implement this to plug in an alternative to the bundled `reqwest` backend — `surf`, a
+/// WASM-friendly `fetch` wrapper, or a mock client for tests — without depending on `reqwest`
+/// yourself. [`Client::with_http_client`](crate::Client::with_http_client) takes any `HttpClient`.
+#[async_trait::async_trait]
+pub trait HttpClient: Clone + Send + Sync {
+    /// The error type this backend's transport can fail with
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Execute a single request and return its response
+    async fn execute(&self, req: Request) -> Result<Response, Self::Error>;
+}
+
+/// The bundled `reqwest`-backed [`HttpClient`], enabled by the default `reqwest` feature
+#[derive(Clone, Debug, Default)]
+#[cfg(feature = "reqwest")]
+pub struct ReqwestHttpClient {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+#[async_trait::async_trait]
+impl HttpClient for ReqwestHttpClient {
+    type Error = reqwest::Error;
+
+    async fn execute(&self, req: Request) -> Result<Response, Self::Error> {
+        let mut builder = match req.method {
+            Method::Get => self.client.get(&req.url),
+            Method::Post => self.client.post(&req.url),
+        };
+        for (key, val) in &req.headers {
+            builder = builder.header(key, val);
+        }
+        if let Some(body) = req.body {
+            builder = builder.body(body);
+        }
+
+        let resp = builder.send().await?;
+        let status = resp.status().as_u16();
+        let headers = resp
+            .headers()
+            .iter()
+            .filter_map(|(key, val)| {
+                val.to_str().ok().map(|val| (key.to_string(), val.to_string()))
+            })
+            .collect();
+        let body = resp.bytes().await?.to_vec();
+        Ok(Response { status, headers, body })
+    }
+}
+
+/// Uninhabited placeholder backend used as `Client`/`Error`'s default type parameter when the
+/// `reqwest` feature is disabled
+///
+/// There's no sensible default `HttpClient` without `reqwest`, so this exists purely so `Client`
+/// and `Error` can keep a default generic parameter (and the ergonomics of writing them bare)
+/// even when built with `--no-default-features`. It can never actually be instantiated.
+#[cfg(not(feature = "reqwest"))]
+#[derive(Debug)]
+pub enum NoHttpClient {}
+
+#[cfg(not(feature = "reqwest"))]
+impl Clone for NoHttpClient {
+    fn clone(&self) -> Self {
+        match *self {}
+    }
+}
+
+#[cfg(not(feature = "reqwest"))]
+#[async_trait::async_trait]
+impl HttpClient for NoHttpClient {
+    type Error = std::convert::Infallible;
+
+    async fn execute(&self, _req: Request) -> Result<Response, Self::Error> {
+        match *self {}
+    }
+}
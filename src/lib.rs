@@ -1,42 +1,102 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
 use std::str::FromStr;
 use time::OffsetDateTime;
 
-#[derive(Debug)]
-pub enum Error {
-    Reqwest {
-        error: reqwest::Error,
+mod eventsub;
+pub mod http;
+mod pagination;
+mod ratelimit;
+mod request;
+mod token;
+pub use eventsub::Event;
+pub use http::HttpClient;
+#[cfg(feature = "reqwest")]
+pub use http::ReqwestHttpClient;
+pub use request::{
+    GetStreamsRequest, GetStreamsRequestBuilder, GetUsersRequest, GetUsersRequestBuilder, Request,
+};
+use http::Method;
+use pagination::Page;
+use ratelimit::RateLimiter;
+use token::TokenStore;
+
+/// `Client`/`Error`'s default backend: `reqwest` when enabled, otherwise an uninhabited
+/// placeholder so the crate still compiles with `--no-default-features` (see
+/// [`http::NoHttpClient`])
+#[cfg(feature = "reqwest")]
+type DefaultHttpClient = ReqwestHttpClient;
+#[cfg(not(feature = "reqwest"))]
+type DefaultHttpClient = http::NoHttpClient;
+
+pub enum Error<C: HttpClient = DefaultHttpClient> {
+    /// The HTTP backend's transport failed
+    Http {
+        error: C::Error,
+    },
+    /// A request or form body couldn't be URL-encoded
+    UrlEncode {
+        error: serde_urlencoded::ser::Error,
+    },
+    /// The EventSub WebSocket transport failed
+    WebSocket {
+        error: Box<tokio_tungstenite::tungstenite::Error>,
     },
-    InvalidClientId {
-        error: reqwest::header::InvalidHeaderValue,
+    /// The EventSub WebSocket stream ended because the connection was closed
+    WebSocketClosed,
+    /// Helix responded with a non-2xx status we don't otherwise retry
+    Status {
+        status: u16,
+        url: String,
     },
-    InvalidOAuthToken {
-        error: reqwest::header::InvalidHeaderValue,
+    /// A response didn't match the shape we expected
+    Json {
+        error: serde_json::Error,
     },
 }
 
-impl From<reqwest::Error> for Error {
-    fn from(error: reqwest::Error) -> Self {
-        Self::Reqwest { error }
+// Hand-written rather than `#[derive(Debug)]`: a derive would add a blanket `C: Debug` bound,
+// but `HttpClient` doesn't require `Debug` on the backend itself, only on `C::Error` (via
+// `C::Error: std::error::Error`). Bounding on the fields we actually print keeps `Error<C>`
+// usable for any `C: HttpClient`.
+impl<C: HttpClient> std::fmt::Debug for Error<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Http { error } => f.debug_struct("Http").field("error", error).finish(),
+            Error::UrlEncode { error } => f.debug_struct("UrlEncode").field("error", error).finish(),
+            Error::WebSocket { error } => f.debug_struct("WebSocket").field("error", error).finish(),
+            Error::WebSocketClosed => write!(f, "WebSocketClosed"),
+            Error::Status { status, url } => {
+                f.debug_struct("Status").field("status", status).field("url", url).finish()
+            }
+            Error::Json { error } => f.debug_struct("Json").field("error", error).finish(),
+        }
     }
 }
 
-impl std::fmt::Display for Error {
+impl<C: HttpClient> std::fmt::Display for Error<C> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Error::Reqwest { error } => write!(f, "reqwest error: {}", error),
-            Error::InvalidClientId { error } => write!(f, "invalid client id: {}", error),
-            Error::InvalidOAuthToken { error } => write!(f, "invalid oauth token: {}", error),
+            Error::Http { error } => write!(f, "http error: {}", error),
+            Error::UrlEncode { error } => write!(f, "url encode error: {}", error),
+            Error::WebSocket { error } => write!(f, "eventsub websocket error: {}", error),
+            Error::WebSocketClosed => write!(f, "eventsub websocket closed unexpectedly"),
+            Error::Status { status, url } => write!(f, "request to {} failed with status {}", url, status),
+            Error::Json { error } => write!(f, "response error: {}", error),
         }
     }
 }
 
-impl std::error::Error for Error {
+impl<C: HttpClient> std::error::Error for Error<C> {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            Error::Reqwest { error } => Some(error),
-            Error::InvalidClientId { error } => Some(error),
-            Error::InvalidOAuthToken { error } => Some(error),
+            Error::Http { error } => Some(error),
+            Error::UrlEncode { error } => Some(error),
+            Error::WebSocket { error } => Some(error),
+            Error::WebSocketClosed => None,
+            Error::Status { .. } => None,
+            Error::Json { error } => Some(error),
         }
     }
 }
@@ -66,6 +126,92 @@ pub struct Users {
     pub viewers: Vec<String>,
 }
 
+/// A chatter's role within a channel, as modeled by the chatters endpoint's buckets
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChatterRole {
+    /// The channel's broadcaster
+    Broadcaster,
+    /// A VIP
+    Vip,
+    /// A moderator
+    Moderator,
+    /// Twitch staff
+    Staff,
+    /// Twitch admin
+    Admin,
+    /// A global moderator
+    GlobalMod,
+    /// Everyone else
+    Viewer,
+}
+
+impl Users {
+    /// The role of `login` within this channel, if they're present in any bucket
+    ///
+    /// Checks buckets in field order (broadcaster, vips, moderators, staff, admins, global_mods,
+    /// viewers), so a login present in more than one resolves to the first of them.
+    pub fn role_of(&self, login: &str) -> Option<ChatterRole> {
+        self.iter_with_roles()
+            .find(|(name, _)| *name == login)
+            .map(|(_, role)| role)
+    }
+
+    /// Iterate over every chatter in this channel, paired with their [`ChatterRole`]
+    pub fn iter_with_roles(&self) -> impl Iterator<Item = (&str, ChatterRole)> {
+        let buckets: [(&[String], ChatterRole); 7] = [
+            (&self.broadcaster, ChatterRole::Broadcaster),
+            (&self.vips, ChatterRole::Vip),
+            (&self.moderators, ChatterRole::Moderator),
+            (&self.staff, ChatterRole::Staff),
+            (&self.admins, ChatterRole::Admin),
+            (&self.global_mods, ChatterRole::GlobalMod),
+            (&self.viewers, ChatterRole::Viewer),
+        ];
+        buckets
+            .into_iter()
+            .flat_map(|(names, role)| names.iter().map(move |name| (name.as_str(), role)))
+    }
+}
+
+/// Whether a stream is currently live
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StreamType {
+    /// The stream is live
+    Live,
+    /// Anything else Helix sends, including the empty string it uses for offline streams
+    Other(String),
+}
+
+impl Default for StreamType {
+    fn default() -> Self {
+        Self::Other(String::new())
+    }
+}
+
+impl Serialize for StreamType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::Live => serializer.serialize_str("live"),
+            Self::Other(other) => serializer.serialize_str(other),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for StreamType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        Ok(match String::deserialize(deserializer)?.as_str() {
+            "live" => Self::Live,
+            other => Self::Other(other.to_string()),
+        })
+    }
+}
+
 /// A Twitch stream
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Stream {
@@ -80,9 +226,9 @@ pub struct Stream {
     #[serde(deserialize_with = "from_str")]
     /// Id of the game being broadcasted
     pub game_id: u64,
-    #[serde(rename = "type")]
-    /// The type of stream (`Some("live")` or None for offline current)
-    pub type_: Option<String>, // TODO enum
+    #[serde(rename = "type", default)]
+    /// Whether the stream is live, per [`StreamType`]
+    pub type_: StreamType,
     /// The title of the stream
     pub title: String,
     /// The viewer count for the stream
@@ -109,157 +255,345 @@ pub struct User {
 pub struct Authenication {
     /// Your twitch Client-ID
     pub client_id: String,
+    /// Your twitch Client-Secret, used to refresh expired tokens
+    pub client_secret: String,
     /// A OAuth token that is associated with the Client-ID
     pub oauth_token: String,
+    /// A refresh token for a User Access Token, if you have one
+    ///
+    /// Leave this `None` to refresh via the client-credentials grant (an App Access Token).
+    pub refresh_token: Option<String>,
 }
 
-/// A clonable Twitch API client
+/// A clonable Twitch API client, generic over its [`HttpClient`] backend
+///
+/// With the default `reqwest` feature enabled, [`Client::new`] gives you a `Client` backed by
+/// `reqwest`. To plug in a different backend (for WASM, a different async runtime, or tests),
+/// use [`Client::with_http_client`] with your own [`HttpClient`] implementation.
 #[derive(Clone)]
-pub struct Client {
-    client: reqwest::Client,
+pub struct Client<C: HttpClient = DefaultHttpClient> {
+    client: C,
+    client_id: String,
+    token: TokenStore<C>,
+    ratelimit: RateLimiter,
 }
 
-impl Client {
+#[cfg(feature = "reqwest")]
+impl Client<ReqwestHttpClient> {
+    /// Create a new Twitch API client backed by `reqwest`, with the provided `Authenication`
+    pub fn new(
+        auth: impl std::borrow::Borrow<Authenication>,
+    ) -> Result<Self, Error<ReqwestHttpClient>> {
+        Self::with_http_client(auth, ReqwestHttpClient::default())
+    }
+}
+
+impl<C: HttpClient + 'static> Client<C> {
     const BASE_URI: &'static str = "https://api.twitch.tv/helix";
 
-    /// Create a new Twitch API client with the provided `Authenication`
-    pub fn new(auth: impl std::borrow::Borrow<Authenication>) -> Result<Self, Error> {
-        reqwest::ClientBuilder::new()
-            // TODO get this at build-time
-            .user_agent("twitchapi/ccd6048 (github.com/museun/twitchapi)")
-            .default_headers({
-                let auth = auth.borrow();
-                let mut map = reqwest::header::HeaderMap::new();
-                map.insert(
-                    "Client-ID",
-                    auth.client_id
-                        .parse()
-                        .map_err(|error| Error::InvalidClientId { error })?,
-                );
-                map.insert(
-                    "Authorization",
-                    format!("Bearer {}", auth.oauth_token)
-                        .parse()
-                        .map_err(|error| Error::InvalidOAuthToken { error })?,
-                );
-                map
-            })
-            .build()
-            .map_err(Into::into)
-            .map(|client| Self { client })
+    /// Create a new Twitch API client using the provided [`HttpClient`] backend
+    pub fn with_http_client(
+        auth: impl std::borrow::Borrow<Authenication>,
+        http_client: C,
+    ) -> Result<Self, Error<C>> {
+        let auth = auth.borrow();
+        Ok(Self {
+            token: TokenStore::new(auth, http_client.clone()),
+            client: http_client,
+            client_id: auth.client_id.clone(),
+            ratelimit: RateLimiter::default(),
+        })
+    }
+
+    /// Ask Twitch how much longer the current token is valid for
+    pub async fn validate_token(&self) -> Result<std::time::Duration, Error<C>> {
+        self.token.validate().await
+    }
+
+    /// Force a refresh of the current token, independent of its expiry
+    pub async fn refresh_token(&self) -> Result<(), Error<C>> {
+        self.token.refresh().await
+    }
+
+    /// The last-seen `Ratelimit-Limit` for this client's app, if any request has completed yet
+    pub fn rate_limit(&self) -> Option<u32> {
+        self.ratelimit.limit()
     }
 
     /// Get a collection of streams for the provided user logins
-    pub async fn get_streams<I>(&self, user_logins: I) -> Result<Vec<Stream>, Error>
+    pub async fn get_streams<I>(&self, user_logins: I) -> Result<Vec<Stream>, Error<C>>
     where
         I: IntoIterator,
-        I::Item: serde::Serialize,
+        I::Item: ToString,
     {
-        #[derive(Deserialize)]
-        struct Data {
-            data: Vec<Stream>,
-        }
-
-        self.get_response("streams", std::iter::repeat("user_login").zip(user_logins))
+        user_logins
+            .into_iter()
+            .fold(GetStreamsRequest::builder(), |b, login| {
+                b.add_user_login(login.to_string())
+            })
+            .send(self)
             .await
-            .map(|data: Data| data.data)
     }
 
     /// Get a collection of streams for the provided user ids
-    pub async fn get_streams_from_id<I>(&self, user_ids: I) -> Result<Vec<Stream>, Error>
+    pub async fn get_streams_from_id<I>(&self, user_ids: I) -> Result<Vec<Stream>, Error<C>>
     where
         I: IntoIterator,
-        I::Item: serde::Serialize,
+        I::Item: ToString,
     {
-        #[derive(Deserialize)]
-        struct Data {
-            data: Vec<Stream>,
-        }
-
-        self.get_response("streams", std::iter::repeat("user_id").zip(user_ids))
+        user_ids
+            .into_iter()
+            .fold(GetStreamsRequest::builder(), |b, id| b.add_user_id(id.to_string()))
+            .send(self)
             .await
-            .map(|data: Data| data.data)
     }
 
     /// Get a collection of users for the provided user names
-    pub async fn get_users<I>(&self, user_logins: I) -> Result<Vec<User>, Error>
+    pub async fn get_users<I>(&self, user_logins: I) -> Result<Vec<User>, Error<C>>
     where
         I: IntoIterator,
-        I::Item: serde::Serialize,
+        I::Item: ToString,
     {
-        #[derive(Deserialize)]
-        struct Data {
-            data: Vec<User>,
-        }
-
-        self.get_response("users", std::iter::repeat("login").zip(user_logins))
+        user_logins
+            .into_iter()
+            .fold(GetUsersRequest::builder(), |b, login| {
+                b.add_login(login.to_string())
+            })
+            .send(self)
             .await
-            .map(|data: Data| data.data)
     }
 
     /// Get a collection of users for the provided user ids
-    pub async fn get_users_from_id<I>(&self, user_ids: I) -> Result<Vec<User>, Error>
+    pub async fn get_users_from_id<I>(&self, user_ids: I) -> Result<Vec<User>, Error<C>>
     where
         I: IntoIterator,
-        I::Item: serde::Serialize,
+        I::Item: ToString,
     {
-        #[derive(Deserialize)]
-        struct Data {
-            data: Vec<User>,
+        user_ids
+            .into_iter()
+            .fold(GetUsersRequest::builder(), |b, id| b.add_id(id.to_string()))
+            .send(self)
+            .await
+    }
+
+    /// Get a lazily-paginated stream of streams for the provided user logins
+    ///
+    /// Unlike [`Client::get_streams`], this follows Helix's `pagination.cursor`
+    /// and fetches subsequent pages on demand as the stream is polled.
+    pub fn get_streams_paginated<I>(
+        &self,
+        user_logins: I,
+    ) -> Pin<Box<dyn futures::Stream<Item = Result<Stream, Error<C>>> + Send>>
+    where
+        I: IntoIterator,
+        I::Item: ToString,
+    {
+        let params = user_logins
+            .into_iter()
+            .map(|login| ("user_login", login.to_string()))
+            .collect();
+        self.get_paginated_stream("streams", params)
+    }
+
+    /// Get a lazily-paginated stream of users for the provided user logins
+    ///
+    /// See [`Client::get_streams_paginated`] for how pagination is driven.
+    pub fn get_users_paginated<I>(
+        &self,
+        user_logins: I,
+    ) -> Pin<Box<dyn futures::Stream<Item = Result<User, Error<C>>> + Send>>
+    where
+        I: IntoIterator,
+        I::Item: ToString,
+    {
+        let params = user_logins
+            .into_iter()
+            .map(|login| ("login", login.to_string()))
+            .collect();
+        self.get_paginated_stream("users", params)
+    }
+
+    /// Drive a Helix endpoint's `pagination.cursor` to completion, yielding items as they arrive
+    ///
+    /// Buffers the current page in a `VecDeque`, yielding from it until exhausted, then issues
+    /// the next request with `after=<cursor>` set. Terminates when Helix stops returning a
+    /// cursor, or a page comes back empty.
+    fn get_paginated_stream<T>(
+        &self,
+        ep: &'static str,
+        params: Vec<(&'static str, String)>,
+    ) -> Pin<Box<dyn futures::Stream<Item = Result<T, Error<C>>> + Send>>
+    where
+        T: for<'de> Deserialize<'de> + Send + 'static,
+    {
+        struct State<C: HttpClient> {
+            client: Client<C>,
+            ep: &'static str,
+            params: Vec<(&'static str, String)>,
+            cursor: Option<String>,
+            done: bool,
         }
 
-        self.get_response("users", std::iter::repeat("id").zip(user_ids))
-            .await
-            .map(|data: Data| data.data)
+        let state = State {
+            client: self.clone(),
+            ep,
+            params,
+            cursor: None,
+            done: false,
+        };
+
+        Box::pin(futures::stream::unfold(
+            (state, VecDeque::<T>::new()),
+            |(mut state, mut buffer)| async move {
+                loop {
+                    if let Some(item) = buffer.pop_front() {
+                        return Some((Ok(item), (state, buffer)));
+                    }
+                    if state.done {
+                        return None;
+                    }
+
+                    let mut params = state.params.clone();
+                    if let Some(cursor) = &state.cursor {
+                        params.push(("after", cursor.clone()));
+                    }
+                    params.push(("first", "100".into()));
+
+                    let result: Result<Page<T>, Error<C>> =
+                        state.client.get_response(state.ep, params).await;
+                    match result {
+                        Ok(page) => {
+                            state.cursor = page.pagination.cursor.filter(|c| !c.is_empty());
+                            buffer = page.data.into_iter().collect();
+                            state.done = state.cursor.is_none() || buffer.is_empty();
+                            if buffer.is_empty() {
+                                return None;
+                            }
+                        }
+                        Err(err) => {
+                            state.done = true;
+                            return Some((Err(err), (state, buffer)));
+                        }
+                    }
+                }
+            },
+        ))
     }
 
     /// Get a collection of users for a Twitch channel
-    pub async fn get_users_for(&self, room: &str) -> Result<Users, Error> {
+    ///
+    /// Unlike the other methods on `Client`, this doesn't go through [`Client::execute_with_retry`]
+    /// and isn't tracked by [`RateLimiter`]: `tmi.twitch.tv/group/user/.../chatters` isn't a Helix
+    /// endpoint, takes no `Bearer`/`Client-ID` auth, and doesn't send back `Ratelimit-*` headers,
+    /// so there's nothing for the shared rate-limit bookkeeping to record.
+    pub async fn get_users_for(&self, room: &str) -> Result<Users, Error<C>> {
         #[derive(Deserialize)]
         struct Data {
             chatter_count: usize,
             chatters: Users,
         }
 
-        let req = self
+        let resp = self
             .client
-            .get(&format!(
-                "https://tmi.twitch.tv/group/user/{}/chatters",
-                room
-            ))
-            .build()?;
-
-        self.client
-            .execute(req)
-            .await?
-            .error_for_status()?
-            .json()
+            .execute(http::Request {
+                method: Method::Get,
+                url: format!("https://tmi.twitch.tv/group/user/{}/chatters", room),
+                headers: HashMap::new(),
+                body: None,
+            })
             .await
-            .map(|data: Data| Users {
+            .map_err(|error| Error::Http { error })?;
+
+        resp.json::<Data>()
+            .map_err(|error| Error::Json { error })
+            .map(|data| Users {
                 room: room.to_string(),
                 chatter_count: data.chatter_count,
                 ..data.chatters
             })
-            .map_err(Into::into)
     }
 
-    async fn get_response<'a, T, M, V>(&self, ep: &str, map: M) -> Result<T, Error>
+    async fn get_response<'a, T, M, V>(&self, ep: &str, map: M) -> Result<T, Error<C>>
     where
         for<'de> T: serde::Deserialize<'de>,
         M: IntoIterator<Item = (&'a str, V)>,
         V: serde::Serialize,
     {
-        let mut req = self.client.get(&format!("{}/{}", Self::BASE_URI, ep));
-        for (key, val) in map {
-            req = req.query(&[(key, val)]);
+        let query: Vec<(&str, V)> = map.into_iter().collect();
+        let query_string =
+            serde_urlencoded::to_string(&query).map_err(|error| Error::UrlEncode { error })?;
+        let url = if query_string.is_empty() {
+            format!("{}/{}", Self::BASE_URI, ep)
+        } else {
+            format!("{}/{}?{}", Self::BASE_URI, ep, query_string)
+        };
+
+        self.execute_with_retry(Method::Get, url, None).await
+    }
+
+    pub(crate) async fn post_response<T, B>(&self, ep: &str, body: &B) -> Result<T, Error<C>>
+    where
+        for<'de> T: serde::Deserialize<'de>,
+        B: serde::Serialize,
+    {
+        let url = format!("{}/{}", Self::BASE_URI, ep);
+        let body = serde_json::to_vec(body).map_err(|error| Error::Json { error })?;
+        self.execute_with_retry(Method::Post, url, Some(body)).await
+    }
+
+    /// Execute a request against `url`, honoring Helix's rate-limit headers, retrying once on an
+    /// expired token (`401`) and, after backing off, on every exhausted bucket (`429`)
+    async fn execute_with_retry<T>(
+        &self,
+        method: Method,
+        url: String,
+        body: Option<Vec<u8>>,
+    ) -> Result<T, Error<C>>
+    where
+        for<'de> T: serde::Deserialize<'de>,
+    {
+        self.ratelimit.wait_for_capacity().await;
+
+        let build_headers = |token: &str| {
+            let mut headers = HashMap::new();
+            headers.insert("Client-ID".to_string(), self.client_id.clone());
+            headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+            if body.is_some() {
+                headers.insert("Content-Type".to_string(), "application/json".to_string());
+            }
+            headers
+        };
+
+        let mut refreshed = false;
+        loop {
+            let token = self.token.access_token().await?;
+            let resp = self
+                .client
+                .execute(http::Request {
+                    method,
+                    url: url.clone(),
+                    headers: build_headers(&token),
+                    body: body.clone(),
+                })
+                .await
+                .map_err(|error| Error::Http { error })?;
+
+            self.ratelimit.record(&resp.headers);
+
+            match resp.status {
+                401 if !refreshed => {
+                    refreshed = true;
+                    self.token.refresh().await?;
+                }
+                429 => {
+                    tokio::time::sleep(self.ratelimit.retry_after(&resp.headers)).await;
+                }
+                status if !(200..300).contains(&status) => {
+                    return Err(Error::Status { status, url });
+                }
+                _ => return resp.json().map_err(|error| Error::Json { error }),
+            }
         }
-        self.client
-            .execute(req.build()?)
-            .await?
-            .error_for_status()?
-            .json()
-            .await
-            .map_err(Into::into)
     }
 }
 
@@ -268,8 +602,11 @@ fn assume_utc_date_time<'de, D>(deser: D) -> Result<OffsetDateTime, D::Error>
 where
     D: serde::de::Deserializer<'de>,
 {
-    time::parse(&(String::deserialize(deser)? + " +0000"), "%FT%TZ %z")
-        .map_err(serde::de::Error::custom)
+    OffsetDateTime::parse(
+        &String::deserialize(deser)?,
+        &time::format_description::well_known::Rfc3339,
+    )
+    .map_err(serde::de::Error::custom)
 }
 
 /// Deserialize using a `FromStr` impl
@@ -283,3 +620,83 @@ where
         .parse()
         .map_err(serde::de::Error::custom)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream_type_of(json: &str) -> StreamType {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn stream_type_maps_live() {
+        assert_eq!(stream_type_of(r#""live""#), StreamType::Live);
+    }
+
+    #[test]
+    fn stream_type_keeps_anything_else_including_empty() {
+        assert_eq!(stream_type_of(r#""""#), StreamType::Other(String::new()));
+        assert_eq!(stream_type_of(r#""vodcast""#), StreamType::Other("vodcast".to_string()));
+    }
+
+    #[test]
+    fn stream_type_serializes_to_twitchs_wire_values_not_rust_tagged_json() {
+        assert_eq!(serde_json::to_string(&StreamType::Live).unwrap(), r#""live""#);
+        assert_eq!(
+            serde_json::to_string(&StreamType::Other("vodcast".to_string())).unwrap(),
+            r#""vodcast""#
+        );
+        assert_eq!(
+            serde_json::to_string(&StreamType::Other(String::new())).unwrap(),
+            r#""""#
+        );
+    }
+
+    #[test]
+    fn stream_type_defaults_to_other_empty_when_absent() {
+        assert_eq!(StreamType::default(), StreamType::Other(String::new()));
+    }
+
+    fn sample_users() -> Users {
+        Users {
+            room: "museun".to_string(),
+            chatter_count: 7,
+            broadcaster: vec!["museun".to_string()],
+            vips: vec!["vip1".to_string()],
+            moderators: vec!["mod1".to_string()],
+            staff: vec!["staff1".to_string()],
+            admins: vec!["admin1".to_string()],
+            global_mods: vec!["gmod1".to_string()],
+            viewers: vec!["viewer1".to_string()],
+        }
+    }
+
+    #[test]
+    fn role_of_finds_the_right_bucket() {
+        let users = sample_users();
+        assert_eq!(users.role_of("museun"), Some(ChatterRole::Broadcaster));
+        assert_eq!(users.role_of("mod1"), Some(ChatterRole::Moderator));
+        assert_eq!(users.role_of("viewer1"), Some(ChatterRole::Viewer));
+        assert_eq!(users.role_of("nobody"), None);
+    }
+
+    #[test]
+    fn iter_with_roles_covers_every_bucket() {
+        let users = sample_users();
+        let mut roles: Vec<_> = users.iter_with_roles().collect();
+        roles.sort_by_key(|(name, _)| *name);
+        assert_eq!(
+            roles,
+            vec![
+                ("admin1", ChatterRole::Admin),
+                ("gmod1", ChatterRole::GlobalMod),
+                ("mod1", ChatterRole::Moderator),
+                ("museun", ChatterRole::Broadcaster),
+                ("staff1", ChatterRole::Staff),
+                ("viewer1", ChatterRole::Viewer),
+                ("vip1", ChatterRole::Vip),
+            ]
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use serde::Deserialize;
+
+/// The `pagination` object Helix attaches to paginated endpoints.
+#[derive(Deserialize, Default)]
+pub(crate) struct Pagination {
+    /// The cursor to pass as `after` to fetch the next page, if any remain.
+    pub cursor: Option<String>,
+}
+
+/// The envelope Helix wraps paginated responses in: `{ data: [...], pagination: {...} }`
+///
+/// `pagination` is absent entirely on endpoints that don't actually paginate (e.g. `/users`),
+/// rather than sent as an empty object, so it defaults rather than being required.
+#[derive(Deserialize)]
+pub(crate) struct Page<T> {
+    pub data: Vec<T>,
+    #[serde(default)]
+    pub pagination: Pagination,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_deserializes_without_a_pagination_object() {
+        let page: Page<u32> = serde_json::from_str(r#"{"data":[1,2,3]}"#).unwrap();
+        assert_eq!(page.data, vec![1, 2, 3]);
+        assert_eq!(page.pagination.cursor, None);
+    }
+
+    #[test]
+    fn page_deserializes_with_a_cursor() {
+        let page: Page<u32> =
+            serde_json::from_str(r#"{"data":[1],"pagination":{"cursor":"abc"}}"#).unwrap();
+        assert_eq!(page.pagination.cursor, Some("abc".to_string()));
+    }
+}
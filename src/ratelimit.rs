@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The last-seen state of Helix's per-app token bucket
+#[derive(Debug, Default)]
+struct Bucket {
+    limit: Option<u32>,
+    remaining: Option<u32>,
+    reset_at: Option<u64>,
+}
+
+/// Tracks Helix's `Ratelimit-*` headers and paces requests to avoid `429`s
+///
+/// Shared between clones of a [`crate::Client`] via an inner `Arc`, so every clone sees the
+/// same bucket state.
+#[derive(Clone, Default)]
+pub(crate) struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+impl RateLimiter {
+    /// The last-seen `Ratelimit-Limit`, if any request has completed yet
+    pub(crate) fn limit(&self) -> Option<u32> {
+        self.bucket.lock().unwrap().limit
+    }
+
+    /// Sleep until the bucket should have capacity again, then preemptively decrement it
+    ///
+    /// The decrement happens before the request is even sent, so concurrent callers don't all
+    /// race past a bucket that's down to its last token; [`RateLimiter::record`] corrects it
+    /// against the real `Ratelimit-Remaining` once the response comes back. If the reset instant
+    /// has already passed by the time a caller gets the lock, the bucket is refilled to `limit`
+    /// right there, rather than leaving `remaining` at `0` until the next response lands —
+    /// otherwise every caller waiting on the exhausted bucket would keep looping on zero-length
+    /// sleeps until some response is recorded.
+    pub(crate) async fn wait_for_capacity(&self) {
+        loop {
+            let sleep_for = {
+                let mut bucket = self.bucket.lock().unwrap();
+                refill_if_elapsed(&mut bucket);
+                match bucket.remaining {
+                    Some(0) => bucket.reset_at.map(seconds_until),
+                    Some(remaining) => {
+                        bucket.remaining = Some(remaining - 1);
+                        None
+                    }
+                    None => None,
+                }
+            };
+            match sleep_for {
+                Some(duration) => tokio::time::sleep(duration).await,
+                None => return,
+            }
+        }
+    }
+
+    /// Record the `Ratelimit-*` headers from a response
+    pub(crate) fn record(&self, headers: &HashMap<String, String>) {
+        let mut bucket = self.bucket.lock().unwrap();
+        if let Some(limit) = header_u32(headers, "ratelimit-limit") {
+            bucket.limit = Some(limit);
+        }
+        if let Some(remaining) = header_u32(headers, "ratelimit-remaining") {
+            bucket.remaining = Some(remaining);
+        }
+        if let Some(reset_at) = header_u64(headers, "ratelimit-reset") {
+            bucket.reset_at = Some(reset_at);
+        }
+    }
+
+    /// How long to back off for after a `429`, preferring `Retry-After` over `Ratelimit-Reset`
+    pub(crate) fn retry_after(&self, headers: &HashMap<String, String>) -> Duration {
+        header_u64(headers, "retry-after")
+            .map(Duration::from_secs)
+            .or_else(|| header_u64(headers, "ratelimit-reset").map(seconds_until))
+            .unwrap_or(Duration::from_secs(1))
+    }
+}
+
+/// Refill an exhausted bucket back to `limit` once its reset instant has passed
+fn refill_if_elapsed(bucket: &mut Bucket) {
+    if bucket.remaining == Some(0) {
+        if let Some(reset_at) = bucket.reset_at {
+            if seconds_until(reset_at) == Duration::ZERO {
+                bucket.remaining = bucket.limit;
+                bucket.reset_at = None;
+            }
+        }
+    }
+}
+
+fn header_u32(headers: &HashMap<String, String>, name: &str) -> Option<u32> {
+    headers.get(name)?.parse().ok()
+}
+
+fn header_u64(headers: &HashMap<String, String>, name: &str) -> Option<u64> {
+    headers.get(name)?.parse().ok()
+}
+
+fn seconds_until(reset_at: u64) -> Duration {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    Duration::from_secs(reset_at.saturating_sub(now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn record_parses_lowercase_ratelimit_headers() {
+        let limiter = RateLimiter::default();
+        limiter.record(&headers(&[
+            ("ratelimit-limit", "800"),
+            ("ratelimit-remaining", "799"),
+            ("ratelimit-reset", "1700000000"),
+        ]));
+        assert_eq!(limiter.limit(), Some(800));
+    }
+
+    #[test]
+    fn record_ignores_unparseable_or_missing_headers() {
+        let limiter = RateLimiter::default();
+        limiter.record(&headers(&[("ratelimit-limit", "not a number")]));
+        assert_eq!(limiter.limit(), None);
+    }
+
+    #[test]
+    fn retry_after_prefers_retry_after_over_ratelimit_reset() {
+        let limiter = RateLimiter::default();
+        let headers = headers(&[("retry-after", "5"), ("ratelimit-reset", "9999999999")]);
+        assert_eq!(limiter.retry_after(&headers), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn retry_after_falls_back_to_one_second_with_no_headers() {
+        let limiter = RateLimiter::default();
+        assert_eq!(limiter.retry_after(&headers(&[])), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn refill_if_elapsed_resets_an_exhausted_bucket_past_its_reset_instant() {
+        let mut bucket = Bucket { limit: Some(800), remaining: Some(0), reset_at: Some(0) };
+        refill_if_elapsed(&mut bucket);
+        assert_eq!(bucket.remaining, Some(800));
+        assert_eq!(bucket.reset_at, None);
+    }
+
+    #[test]
+    fn refill_if_elapsed_leaves_a_bucket_with_capacity_untouched() {
+        let mut bucket = Bucket { limit: Some(800), remaining: Some(5), reset_at: Some(0) };
+        refill_if_elapsed(&mut bucket);
+        assert_eq!(bucket.remaining, Some(5));
+    }
+}
@@ -0,0 +1,213 @@
+use crate::http::HttpClient;
+use crate::{Client, Error, Stream, User};
+
+/// A strongly-typed Helix request, built via `<Type>::builder()`
+///
+/// [`Client::send`] drives any `Request`: it hits `PATH`, attaches `query_pairs`, and unwraps
+/// the `{ "data": ... }` envelope Helix wraps list responses in.
+pub trait Request {
+    /// The Helix endpoint path, relative to `/helix`
+    const PATH: &'static str;
+    /// What the response's `data` field deserializes into
+    type Response: for<'de> serde::Deserialize<'de>;
+    /// The query parameters this request carries
+    fn query_pairs(&self) -> Vec<(&str, String)>;
+}
+
+#[derive(serde::Deserialize)]
+struct Envelope<T> {
+    data: T,
+}
+
+impl<C: HttpClient + 'static> Client<C> {
+    /// Send a typed [`Request`], returning its unwrapped `data`
+    pub async fn send<R: Request>(&self, req: &R) -> Result<R::Response, Error<C>> {
+        self.get_response::<Envelope<R::Response>, _, _>(R::PATH, req.query_pairs())
+            .await
+            .map(|envelope| envelope.data)
+    }
+}
+
+/// Request for `GET /helix/streams`, built with [`GetStreamsRequest::builder`]
+#[derive(Debug, Clone, Default)]
+pub struct GetStreamsRequest {
+    user_logins: Vec<String>,
+    user_ids: Vec<String>,
+    game_ids: Vec<String>,
+    languages: Vec<String>,
+    first: Option<u32>,
+}
+
+impl GetStreamsRequest {
+    /// Start building a `GetStreamsRequest`
+    pub fn builder() -> GetStreamsRequestBuilder {
+        GetStreamsRequestBuilder::default()
+    }
+}
+
+impl Request for GetStreamsRequest {
+    const PATH: &'static str = "streams";
+    type Response = Vec<Stream>;
+
+    fn query_pairs(&self) -> Vec<(&str, String)> {
+        let mut pairs = Vec::new();
+        pairs.extend(self.user_logins.iter().map(|v| ("user_login", v.clone())));
+        pairs.extend(self.user_ids.iter().map(|v| ("user_id", v.clone())));
+        pairs.extend(self.game_ids.iter().map(|v| ("game_id", v.clone())));
+        pairs.extend(self.languages.iter().map(|v| ("language", v.clone())));
+        if let Some(first) = self.first {
+            pairs.push(("first", first.to_string()));
+        }
+        pairs
+    }
+}
+
+/// Builder for [`GetStreamsRequest`]
+#[derive(Debug, Clone, Default)]
+pub struct GetStreamsRequestBuilder {
+    inner: GetStreamsRequest,
+}
+
+impl GetStreamsRequestBuilder {
+    /// Filter by a broadcaster's user login
+    pub fn add_user_login(mut self, login: impl Into<String>) -> Self {
+        self.inner.user_logins.push(login.into());
+        self
+    }
+
+    /// Filter by a broadcaster's user id
+    pub fn add_user_id(mut self, id: impl ToString) -> Self {
+        self.inner.user_ids.push(id.to_string());
+        self
+    }
+
+    /// Filter by the game/category being broadcast
+    pub fn add_game_id(mut self, id: impl ToString) -> Self {
+        self.inner.game_ids.push(id.to_string());
+        self
+    }
+
+    /// Filter by stream language
+    pub fn add_language(mut self, language: impl Into<String>) -> Self {
+        self.inner.languages.push(language.into());
+        self
+    }
+
+    /// Maximum results per page, capped at Helix's limit of 100
+    pub fn first(mut self, first: u32) -> Self {
+        self.inner.first = Some(first.min(100));
+        self
+    }
+
+    /// Finalize the request without sending it
+    pub fn build(self) -> GetStreamsRequest {
+        self.inner
+    }
+
+    /// Build and send the request
+    pub async fn send<C: HttpClient + 'static>(self, client: &Client<C>) -> Result<Vec<Stream>, Error<C>> {
+        client.send(&self.build()).await
+    }
+}
+
+/// Request for `GET /helix/users`, built with [`GetUsersRequest::builder`]
+#[derive(Debug, Clone, Default)]
+pub struct GetUsersRequest {
+    logins: Vec<String>,
+    ids: Vec<String>,
+}
+
+impl GetUsersRequest {
+    /// Start building a `GetUsersRequest`
+    pub fn builder() -> GetUsersRequestBuilder {
+        GetUsersRequestBuilder::default()
+    }
+}
+
+impl Request for GetUsersRequest {
+    const PATH: &'static str = "users";
+    type Response = Vec<User>;
+
+    fn query_pairs(&self) -> Vec<(&str, String)> {
+        let mut pairs = Vec::new();
+        pairs.extend(self.logins.iter().map(|v| ("login", v.clone())));
+        pairs.extend(self.ids.iter().map(|v| ("id", v.clone())));
+        pairs
+    }
+}
+
+/// Builder for [`GetUsersRequest`]
+#[derive(Debug, Clone, Default)]
+pub struct GetUsersRequestBuilder {
+    inner: GetUsersRequest,
+}
+
+impl GetUsersRequestBuilder {
+    /// Filter by login name
+    pub fn add_login(mut self, login: impl Into<String>) -> Self {
+        self.inner.logins.push(login.into());
+        self
+    }
+
+    /// Filter by user id
+    pub fn add_id(mut self, id: impl ToString) -> Self {
+        self.inner.ids.push(id.to_string());
+        self
+    }
+
+    /// Finalize the request without sending it
+    pub fn build(self) -> GetUsersRequest {
+        self.inner
+    }
+
+    /// Build and send the request
+    pub async fn send<C: HttpClient + 'static>(self, client: &Client<C>) -> Result<Vec<User>, Error<C>> {
+        client.send(&self.build()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_streams_request_query_pairs_follow_field_order() {
+        let req = GetStreamsRequest::builder()
+            .add_user_login("museun")
+            .add_user_id(1234)
+            .add_game_id(5678)
+            .add_language("en")
+            .first(10)
+            .build();
+
+        assert_eq!(
+            req.query_pairs(),
+            vec![
+                ("user_login", "museun".to_string()),
+                ("user_id", "1234".to_string()),
+                ("game_id", "5678".to_string()),
+                ("language", "en".to_string()),
+                ("first", "10".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn get_streams_request_first_is_capped_at_helix_limit() {
+        let req = GetStreamsRequest::builder().first(500).build();
+        assert_eq!(req.query_pairs(), vec![("first", "100".to_string())]);
+    }
+
+    #[test]
+    fn get_users_request_query_pairs_follow_field_order() {
+        let req = GetUsersRequest::builder()
+            .add_login("museun")
+            .add_id(1234)
+            .build();
+
+        assert_eq!(
+            req.query_pairs(),
+            vec![("login", "museun".to_string()), ("id", "1234".to_string())]
+        );
+    }
+}
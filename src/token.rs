@@ -0,0 +1,151 @@
+use crate::http::{HttpClient, Method, Request};
+use crate::{Authenication, Error};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+const VALIDATE_URL: &str = "https://id.twitch.tv/oauth2/validate";
+const TOKEN_URL: &str = "https://id.twitch.tv/oauth2/token";
+
+/// How close to expiry we proactively refresh, rather than waiting to be rejected.
+const REFRESH_THRESHOLD: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+struct Token {
+    access_token: String,
+    client_id: String,
+    client_secret: String,
+    refresh_token: Option<String>,
+    expires_at: Option<Instant>,
+}
+
+#[derive(Deserialize)]
+struct ValidateResponse {
+    expires_in: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+    #[serde(default)]
+    refresh_token: Option<String>,
+}
+
+/// Holds the current access token and refreshes it against `id.twitch.tv`
+///
+/// Shared between clones of a [`crate::Client`] via an inner `Arc`, so a refresh triggered by
+/// one clone is visible to all the others. Goes through the same [`HttpClient`] backend as the
+/// rest of the client, rather than its own `reqwest::Client`.
+#[derive(Clone)]
+pub(crate) struct TokenStore<C> {
+    inner: Arc<RwLock<Token>>,
+    http: C,
+}
+
+impl<C: HttpClient> TokenStore<C> {
+    pub(crate) fn new(auth: &Authenication, http: C) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Token {
+                access_token: auth.oauth_token.clone(),
+                client_id: auth.client_id.clone(),
+                client_secret: auth.client_secret.clone(),
+                refresh_token: auth.refresh_token.clone(),
+                expires_at: None,
+            })),
+            http,
+        }
+    }
+
+    /// The current access token, refreshing first if it's close to expiring
+    pub(crate) async fn access_token(&self) -> Result<String, Error<C>> {
+        if self.needs_refresh().await {
+            self.refresh().await?;
+        }
+        Ok(self.inner.read().await.access_token.clone())
+    }
+
+    async fn needs_refresh(&self) -> bool {
+        matches!(
+            self.inner.read().await.expires_at,
+            Some(at) if Instant::now() + REFRESH_THRESHOLD >= at
+        )
+    }
+
+    /// Ask Twitch how much longer the current token is valid for
+    ///
+    /// Calls `GET https://id.twitch.tv/oauth2/validate`, recording the returned `expires_in` so
+    /// future requests know when to proactively refresh.
+    pub(crate) async fn validate(&self) -> Result<Duration, Error<C>> {
+        let token = self.inner.read().await.access_token.clone();
+        let resp = self
+            .http
+            .execute(Request {
+                method: Method::Get,
+                url: VALIDATE_URL.into(),
+                headers: HashMap::from([(
+                    "Authorization".into(),
+                    format!("OAuth {}", token),
+                )]),
+                body: None,
+            })
+            .await
+            .map_err(|error| Error::Http { error })?;
+
+        let validated: ValidateResponse = resp.json().map_err(|error| Error::Json { error })?;
+        let expires_in = Duration::from_secs(validated.expires_in);
+        self.inner.write().await.expires_at = Some(Instant::now() + expires_in);
+        Ok(expires_in)
+    }
+
+    /// Exchange the refresh token (or client credentials, if we have none) for a new access token
+    ///
+    /// Calls `POST https://id.twitch.tv/oauth2/token` and updates the shared token in place.
+    pub(crate) async fn refresh(&self) -> Result<(), Error<C>> {
+        let (client_id, client_secret, refresh_token) = {
+            let token = self.inner.read().await;
+            (
+                token.client_id.clone(),
+                token.client_secret.clone(),
+                token.refresh_token.clone(),
+            )
+        };
+
+        let mut form: Vec<(&str, &str)> = match &refresh_token {
+            Some(refresh_token) => vec![
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+            ],
+            None => vec![("grant_type", "client_credentials")],
+        };
+        form.push(("client_id", &client_id));
+        form.push(("client_secret", &client_secret));
+        let body = serde_urlencoded::to_string(&form).map_err(|error| Error::UrlEncode { error })?;
+
+        let resp = self
+            .http
+            .execute(Request {
+                method: Method::Post,
+                url: TOKEN_URL.into(),
+                headers: HashMap::from([(
+                    "Content-Type".into(),
+                    "application/x-www-form-urlencoded".into(),
+                )]),
+                body: Some(body.into_bytes()),
+            })
+            .await
+            .map_err(|error| Error::Http { error })?;
+
+        let refreshed: TokenResponse = resp.json().map_err(|error| Error::Json { error })?;
+
+        let mut token = self.inner.write().await;
+        token.access_token = refreshed.access_token;
+        token.expires_at = Some(Instant::now() + Duration::from_secs(refreshed.expires_in));
+        if let Some(refresh_token) = refreshed.refresh_token {
+            token.refresh_token = Some(refresh_token);
+        }
+        Ok(())
+    }
+}